@@ -1,19 +1,28 @@
 use core::sync::atomic::Ordering;
-use crossbeam_channel::Sender;
+use crossbeam_channel::{Receiver, Sender, select, unbounded};
 use serialport::{DataBits, FlowControl, Parity, SerialPort, StopBits};
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::mem::size_of;
+use std::thread::JoinHandle;
 use std::time::Duration;
 
 use crate::SensorData;
 use crate::VALS_PER_LINE;
+use crate::{Msg, MsgLevel, TeleCategory, WireFormat};
 
-const IN_PORT_PATH: &str = "/dev/tty.usbmodem0xC0DECAFE1";
-const OUT_PORT_PATH: &str = "/dev/tty.usbmodem0xC0DECAFE3";
-
-const BAUD_RATE: u32 = 115200;
+/// Human-readable names of every serial port the OS currently enumerates, for populating the
+/// connection ComboBoxes. Returns an empty list (rather than erroring) if enumeration fails.
+pub fn list_port_names() -> Vec<String> {
+    serialport::available_ports()
+        .map(|ports| ports.into_iter().map(|p| p.port_name).collect())
+        .unwrap_or_default()
+}
 
-fn open_serial_port(port_path: &str) -> serialport::Result<Box<dyn SerialPort>> {
-    serialport::new(port_path, BAUD_RATE)
+pub fn open_serial_port(
+    port_path: &str,
+    baud_rate: u32,
+) -> serialport::Result<Box<dyn SerialPort>> {
+    serialport::new(port_path, baud_rate)
         .data_bits(DataBits::Eight)
         .flow_control(FlowControl::None)
         .parity(Parity::None)
@@ -22,56 +31,482 @@ fn open_serial_port(port_path: &str) -> serialport::Result<Box<dyn SerialPort>>
         .open()
 }
 
-pub fn open_out_port() -> serialport::Result<Box<dyn SerialPort>> {
-    open_serial_port(OUT_PORT_PATH)
+fn notify(msg_channel: &Sender<Msg>, level: MsgLevel, text: String) {
+    msg_channel.send(Msg { level, text }).unwrap();
 }
 
-pub fn input_thread(data_channel: Sender<SensorData>, msg_channel: Sender<String>) {
-    let mut log_port = match open_serial_port(IN_PORT_PATH) {
+/// Reads telemetry from `port_path` in the given `wire_format` until a value arrives on
+/// `stop_receiver`.
+///
+/// Connection and parse failures are reported on `msg_channel` rather than panicking, so an
+/// unplugged or misconfigured device just ends this reader thread instead of taking the whole
+/// app down with it. A failed open is not reported back to the thread that spawned the reader,
+/// which keeps its output port open and stays connected.
+pub fn input_thread(
+    port_path: String,
+    baud_rate: u32,
+    wire_format: WireFormat,
+    data_channel: Sender<SensorData>,
+    msg_channel: Sender<Msg>,
+    stop_receiver: Receiver<()>,
+) {
+    let mut log_port = match open_serial_port(&port_path, baud_rate) {
         Ok(port) => port,
         Err(e) => {
-            msg_channel
-                .send(format!("Failed to open log port {}: {:?}", IN_PORT_PATH, e))
-                .unwrap();
+            notify(
+                &msg_channel,
+                MsgLevel::Error,
+                format!("Failed to open log port {}: {:?}", port_path, e),
+            );
             return;
         }
     };
 
-    let reader = BufReader::new(log_port.as_mut());
+    match wire_format {
+        WireFormat::Csv => read_csv_lines(log_port.as_mut(), &data_channel, &msg_channel, &stop_receiver),
+        WireFormat::CobsBinary => {
+            read_cobs_frames(log_port.as_mut(), &data_channel, &msg_channel, &stop_receiver)
+        }
+    }
+
+    notify(
+        &msg_channel,
+        MsgLevel::Info,
+        format!("Log port {} reader thread exiting.", port_path),
+    );
+}
+
+fn read_csv_lines(
+    port: &mut dyn SerialPort,
+    data_channel: &Sender<SensorData>,
+    msg_channel: &Sender<Msg>,
+    stop_receiver: &Receiver<()>,
+) {
+    let mut reader = BufReader::new(port);
+    let mut line = String::new();
+
+    loop {
+        if stop_receiver.try_recv().is_ok() {
+            break;
+        }
+
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {
+                let values: Vec<f64> = line
+                    .trim_end()
+                    .split(',')
+                    .filter_map(|s| {
+                        s.parse().ok().and_then(|val: f64| {
+                            if val.is_finite() {
+                                Some(val)
+                            } else {
+                                notify(
+                                    msg_channel,
+                                    MsgLevel::Warning,
+                                    format!("Skipping non-finite value: {}", s),
+                                );
+                                None
+                            }
+                        })
+                    })
+                    .collect();
 
-    for line_result in reader.lines() {
-        if let Ok(line) = line_result {
-            let values: Vec<f64> = line
-                .split(',')
-                .filter_map(|s| {
-                    s.parse().ok().and_then(|val: f64| {
+                let val_num = VALS_PER_LINE.load(Ordering::Acquire);
+                if values.len() == val_num {
+                    let sensor_data = SensorData {
+                        values,
+                        tele_category: None,
+                    };
+                    data_channel.send(sensor_data).unwrap();
+                } else if val_num != 0 {
+                    notify(
+                        msg_channel,
+                        MsgLevel::Warning,
+                        format!(
+                            "Skipping line with unexpected number of values (expected {}, got {}): '{}'",
+                            val_num,
+                            values.len(),
+                            line.trim_end()
+                        ),
+                    );
+                }
+            }
+            // The read timeout just gives us a chance to check stop_receiver; not an error.
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => continue,
+            Err(_) => continue,
+        }
+    }
+}
+
+/// Decodes a single COBS-encoded frame (with the trailing `0x00` delimiter already stripped) in
+/// place, returning the length of the recovered payload written to the front of `buf`.
+fn cobs_decode(buf: &mut [u8]) -> usize {
+    let len = buf.len();
+    let mut read = 0;
+    let mut write = 0;
+
+    while read < len {
+        let code = buf[read] as usize;
+        read += 1;
+        let block_len = code.saturating_sub(1);
+        for _ in 0..block_len {
+            if read >= len {
+                break;
+            }
+            buf[write] = buf[read];
+            write += 1;
+            read += 1;
+        }
+        // A code byte less than 0xFF implies a zero between blocks, except at the very end.
+        if code != 0xFF && read < len {
+            buf[write] = 0;
+            write += 1;
+        }
+    }
+
+    write
+}
+
+/// Drains complete COBS frames (delimited by `0x00`) from the front of `accum`, COBS-decoding
+/// each one in place and returning the recovered payloads. Bytes after the last delimiter are
+/// left in `accum` for the next read. Pulled out of `read_cobs_frames` so the framing/decoding
+/// logic can be unit-tested without a real `SerialPort`.
+fn drain_cobs_frames(accum: &mut Vec<u8>) -> Vec<Vec<u8>> {
+    let mut frames = Vec::new();
+    while let Some(pos) = accum.iter().position(|&b| b == 0) {
+        let mut frame: Vec<u8> = accum[..pos].to_vec();
+        accum.drain(..=pos);
+        if frame.is_empty() {
+            continue;
+        }
+        let payload_len = cobs_decode(&mut frame);
+        frame.truncate(payload_len);
+        frames.push(frame);
+    }
+    frames
+}
+
+/// Upper bound on how much unframed input `read_cobs_frames` will buffer while waiting for a
+/// `0x00` delimiter. A misconfigured baud rate can garble the stream so thoroughly that no zero
+/// byte ever turns up; without a cap `accum` would grow for as long as the port stays open.
+const MAX_COBS_ACCUM_LEN: usize = 4096;
+
+/// Reads length-delimited COBS frames from `port`, splitting the raw byte stream on the `0x00`
+/// delimiter COBS guarantees never appears inside an encoded frame. Each frame is COBS-decoded
+/// and interpreted as a packed little-endian `[f32; VALS_PER_LINE]` payload. A malformed frame
+/// just gets skipped; the next `0x00` resynchronizes the stream.
+fn read_cobs_frames(
+    port: &mut dyn SerialPort,
+    data_channel: &Sender<SensorData>,
+    msg_channel: &Sender<Msg>,
+    stop_receiver: &Receiver<()>,
+) {
+    let mut accum: Vec<u8> = Vec::new();
+    let mut read_buf = [0u8; 256];
+
+    loop {
+        if stop_receiver.try_recv().is_ok() {
+            break;
+        }
+
+        match port.read(&mut read_buf) {
+            Ok(0) => break,
+            Ok(n) => {
+                accum.extend_from_slice(&read_buf[..n]);
+
+                for payload in drain_cobs_frames(&mut accum) {
+                    let val_num = VALS_PER_LINE.load(Ordering::Acquire);
+                    if val_num == 0 {
+                        continue;
+                    }
+                    if payload.len() != val_num * size_of::<f32>() {
+                        notify(
+                            msg_channel,
+                            MsgLevel::Warning,
+                            format!(
+                                "Skipping binary frame with unexpected length (expected {} bytes for {} channels, got {})",
+                                val_num * size_of::<f32>(),
+                                val_num,
+                                payload.len()
+                            ),
+                        );
+                        continue;
+                    }
+
+                    let mut values = Vec::with_capacity(val_num);
+                    let mut malformed = false;
+                    for chunk in payload.chunks_exact(size_of::<f32>()) {
+                        let val = f32::from_le_bytes(chunk.try_into().unwrap()) as f64;
                         if val.is_finite() {
-                            Some(val)
+                            values.push(val);
                         } else {
-                            msg_channel
-                                .send(format!("Skipping non-finite value: {}", s))
-                                .unwrap();
-                            None
+                            notify(
+                                msg_channel,
+                                MsgLevel::Warning,
+                                format!("Skipping non-finite value: {}", val),
+                            );
+                            malformed = true;
+                            break;
                         }
-                    })
-                })
-                .collect();
-
-            let val_num = VALS_PER_LINE.load(Ordering::Acquire);
-            if values.len() == val_num {
-                let sensor_data = SensorData { values };
-                data_channel.send(sensor_data).unwrap();
-            } else if val_num != 0 {
-                msg_channel.send(format!(
-                    "Skipping line with unexpected number of values (expected {}, got {}): '{}'",
-                    val_num,
-                    values.len(),
-                    line
-                )).unwrap();
+                    }
+                    if !malformed {
+                        data_channel
+                            .send(SensorData {
+                                values,
+                                tele_category: None,
+                            })
+                            .unwrap();
+                    }
+                }
+
+                if accum.len() > MAX_COBS_ACCUM_LEN {
+                    notify(
+                        msg_channel,
+                        MsgLevel::Warning,
+                        format!(
+                            "Discarding {} bytes of unframed COBS input with no delimiter; check the baud rate.",
+                            accum.len()
+                        ),
+                    );
+                    accum.clear();
+                }
             }
+            // The read timeout just gives us a chance to check stop_receiver; not an error.
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => continue,
+            Err(_) => continue,
         }
     }
-    msg_channel
-        .send("Standard input reader thread exiting.".to_string())
-        .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cobs_decode_no_embedded_zero() {
+        let mut buf = [5, 0x11, 0x22, 0x33, 0x44];
+        let len = cobs_decode(&mut buf);
+        assert_eq!(&buf[..len], &[0x11, 0x22, 0x33, 0x44]);
+    }
+
+    #[test]
+    fn cobs_decode_single_zero() {
+        let mut buf = [1, 1];
+        let len = cobs_decode(&mut buf);
+        assert_eq!(&buf[..len], &[0x00]);
+    }
+
+    #[test]
+    fn cobs_decode_two_zeroes() {
+        let mut buf = [1, 1, 1];
+        let len = cobs_decode(&mut buf);
+        assert_eq!(&buf[..len], &[0x00, 0x00]);
+    }
+
+    #[test]
+    fn cobs_decode_embedded_zero() {
+        let mut buf = [3, 0x11, 0x22, 2, 0x33];
+        let len = cobs_decode(&mut buf);
+        assert_eq!(&buf[..len], &[0x11, 0x22, 0x00, 0x33]);
+    }
+
+    #[test]
+    fn drain_cobs_frames_splits_complete_frames_and_keeps_partial_tail() {
+        let mut accum = vec![5, 0x11, 0x22, 0x33, 0x44, 0, 1, 1, 0, 9, 9];
+        let frames = drain_cobs_frames(&mut accum);
+        assert_eq!(frames, vec![vec![0x11, 0x22, 0x33, 0x44], vec![0x00]]);
+        assert_eq!(accum, vec![9, 9]);
+    }
+
+    #[test]
+    fn drain_cobs_frames_skips_empty_frames() {
+        let mut accum = vec![0, 0, 5, 0x11, 0x22, 0x33, 0x44, 0];
+        let frames = drain_cobs_frames(&mut accum);
+        assert_eq!(frames, vec![vec![0x11, 0x22, 0x33, 0x44]]);
+        assert!(accum.is_empty());
+    }
+}
+
+/// Actions the UI can ask the device owner thread to perform. Keeping these as typed commands
+/// means `PlotterApp` never touches a port directly, so a slow or blocked write can't stall the
+/// render loop.
+pub enum DeviceCmd {
+    Connect {
+        in_port: String,
+        out_port: String,
+        baud: u32,
+        wire_format: WireFormat,
+    },
+    SetMode(TeleCategory),
+    SetBaud(u32),
+    Disconnect,
+    Reconnect,
+}
+
+/// Reports the device thread's actual connection state back to `PlotterApp`, so the UI reflects
+/// whether a port is really open rather than just that a connect/reconnect was requested.
+pub enum DeviceEvent {
+    Connected,
+    ConnectFailed,
+    Disconnected,
+}
+
+struct DeviceState {
+    tele_port: Option<Box<dyn SerialPort>>,
+    reader_stop: Option<Sender<()>>,
+    reader_handle: Option<JoinHandle<()>>,
+    in_port: Option<String>,
+    out_port: Option<String>,
+    baud: u32,
+    wire_format: WireFormat,
+    mode: TeleCategory,
+}
+
+/// Owns the output port and the input reader thread, and is the only thing that ever opens or
+/// calls `write_all` on the output port; the input port is opened by the reader thread it
+/// spawns. Drains `cmd_receiver` until the sender side is
+/// dropped, which is also the thread's shutdown signal.
+pub fn device_thread(
+    cmd_receiver: Receiver<DeviceCmd>,
+    data_sender: Sender<SensorData>,
+    msg_sender: Sender<Msg>,
+    event_sender: Sender<DeviceEvent>,
+) {
+    let mut state = DeviceState {
+        tele_port: None,
+        reader_stop: None,
+        reader_handle: None,
+        in_port: None,
+        out_port: None,
+        baud: 115_200,
+        wire_format: WireFormat::Csv,
+        mode: TeleCategory::None,
+    };
+
+    loop {
+        select! {
+            recv(cmd_receiver) -> cmd => match cmd {
+                Ok(cmd) => handle_device_cmd(cmd, &mut state, &data_sender, &msg_sender, &event_sender),
+                Err(_) => break,
+            },
+        }
+    }
+
+    disconnect_device(&mut state, &msg_sender, &event_sender);
+}
+
+fn handle_device_cmd(
+    cmd: DeviceCmd,
+    state: &mut DeviceState,
+    data_sender: &Sender<SensorData>,
+    msg_sender: &Sender<Msg>,
+    event_sender: &Sender<DeviceEvent>,
+) {
+    match cmd {
+        DeviceCmd::Connect {
+            in_port,
+            out_port,
+            baud,
+            wire_format,
+        } => {
+            state.in_port = Some(in_port);
+            state.out_port = Some(out_port);
+            state.baud = baud;
+            state.wire_format = wire_format;
+            connect_device(state, data_sender, msg_sender, event_sender);
+        }
+        DeviceCmd::SetMode(mode) => {
+            state.mode = mode;
+            write_mode(state, msg_sender);
+        }
+        DeviceCmd::SetBaud(baud) => {
+            state.baud = baud;
+        }
+        DeviceCmd::Disconnect => disconnect_device(state, msg_sender, event_sender),
+        DeviceCmd::Reconnect => connect_device(state, data_sender, msg_sender, event_sender),
+    }
+}
+
+fn write_mode(state: &mut DeviceState, msg_sender: &Sender<Msg>) {
+    if let Some(port) = state.tele_port.as_mut() {
+        if let Err(e) = port.write_all(format!("{}\n", state.mode as u8).as_bytes()) {
+            notify(
+                msg_sender,
+                MsgLevel::Error,
+                format!("Failed to send mode to device: {:?}", e),
+            );
+        }
+    }
+}
+
+fn connect_device(
+    state: &mut DeviceState,
+    data_sender: &Sender<SensorData>,
+    msg_sender: &Sender<Msg>,
+    event_sender: &Sender<DeviceEvent>,
+) {
+    disconnect_device(state, msg_sender, event_sender);
+
+    let (Some(in_port), Some(out_port)) = (state.in_port.clone(), state.out_port.clone()) else {
+        notify(
+            msg_sender,
+            MsgLevel::Warning,
+            "Select both an input and output port before connecting.".to_string(),
+        );
+        let _ = event_sender.send(DeviceEvent::ConnectFailed);
+        return;
+    };
+
+    match open_serial_port(&out_port, state.baud) {
+        Ok(port) => state.tele_port = Some(port),
+        Err(e) => {
+            notify(
+                msg_sender,
+                MsgLevel::Error,
+                format!("Failed to open output port {}: {:?}", out_port, e),
+            );
+            let _ = event_sender.send(DeviceEvent::ConnectFailed);
+            return;
+        }
+    }
+
+    let (stop_tx, stop_rx) = unbounded::<()>();
+    let reader_data_sender = data_sender.clone();
+    let reader_msg_sender = msg_sender.clone();
+    let baud = state.baud;
+    let wire_format = state.wire_format;
+    let handle = std::thread::spawn(move || {
+        input_thread(
+            in_port,
+            baud,
+            wire_format,
+            reader_data_sender,
+            reader_msg_sender,
+            stop_rx,
+        );
+    });
+
+    state.reader_stop = Some(stop_tx);
+    state.reader_handle = Some(handle);
+    write_mode(state, msg_sender);
+    let _ = event_sender.send(DeviceEvent::Connected);
+}
+
+fn disconnect_device(
+    state: &mut DeviceState,
+    msg_sender: &Sender<Msg>,
+    event_sender: &Sender<DeviceEvent>,
+) {
+    if let Some(stop_tx) = state.reader_stop.take() {
+        let _ = stop_tx.send(());
+    }
+    if let Some(handle) = state.reader_handle.take() {
+        let _ = handle.join();
+    }
+    if state.tele_port.take().is_some() {
+        notify(msg_sender, MsgLevel::Info, "Disconnected.".to_string());
+        let _ = event_sender.send(DeviceEvent::Disconnected);
+    }
 }
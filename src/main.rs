@@ -1,5 +1,5 @@
 use core::sync::atomic::{AtomicUsize, Ordering};
-use crossbeam_channel::{Receiver, unbounded};
+use crossbeam_channel::{Receiver, Sender, unbounded};
 use eframe::egui;
 use egui::{CentralPanel, ScrollArea, containers::TopBottomPanel};
 use egui::{Color32, ViewportBuilder};
@@ -7,13 +7,17 @@ use egui_plotter::EguiBackend;
 use plotters::prelude::full_palette::*;
 use plotters::prelude::*;
 use ringbuffer::{ConstGenericRingBuffer, RingBuffer};
-use serialport::SerialPort;
-use std::io::Write;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 use strum::IntoEnumIterator;
 use strum_macros::{AsRefStr, EnumIter};
 
 mod io;
+mod record;
+
+const PLAYBACK_SPEEDS: [f64; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
 
 static VALS_PER_LINE: AtomicUsize = AtomicUsize::new(0);
 
@@ -31,9 +35,13 @@ const COLORS: [plotters::style::RGBColor; 9] = [
 
 const MAX_HISTORY_LEN: usize = 512;
 const MAX_MSGS: usize = 16;
+const BAUD_RATES: [u32; 8] = [
+    9600, 19200, 38400, 57600, 115200, 230400, 460800, 921600,
+];
+const DEFAULT_BAUD_RATE: u32 = 115200;
 
 // TODO: keep in sync with simplest_drone, move to shared crate one day
-#[derive(Debug, EnumIter, AsRefStr, PartialEq, Clone, Copy)]
+#[derive(Debug, EnumIter, AsRefStr, PartialEq, Clone, Copy, Serialize, Deserialize)]
 enum TeleCategory {
     None = 0,
     Imu,
@@ -43,11 +51,90 @@ enum TeleCategory {
     Dshot,
 }
 
+impl TeleCategory {
+    fn from_u8(val: u8) -> Option<Self> {
+        TeleCategory::iter().find(|mode| *mode as u8 == val)
+    }
+}
+
+/// Wire encoding the reader thread expects on the input port.
+#[derive(Debug, EnumIter, AsRefStr, PartialEq, Clone, Copy, Serialize, Deserialize)]
+enum WireFormat {
+    Csv,
+    CobsBinary,
+}
+
+/// UI preferences persisted across runs via `eframe`'s storage (`PlotterApp::save`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Settings {
+    tele_mode: TeleCategory,
+    selected_in_port: Option<String>,
+    selected_out_port: Option<String>,
+    baud_rate: u32,
+    wire_format: WireFormat,
+    y_lock: bool,
+    locked_y_min: f64,
+    locked_y_max: f64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            tele_mode: TeleCategory::None,
+            selected_in_port: None,
+            selected_out_port: None,
+            baud_rate: DEFAULT_BAUD_RATE,
+            wire_format: WireFormat::Csv,
+            y_lock: false,
+            locked_y_min: 0.0,
+            locked_y_max: 1.0,
+        }
+    }
+}
+
+/// `tele_category` is `Some` when the producer knows which mode this sample belongs to (playback
+/// replaying a recording that may span more than one mode) and `None` when it was always implied
+/// by whatever mode the device is currently in (live CSV/COBS reads).
 #[derive(Debug, Clone)]
 struct SensorData {
     values: Vec<f64>,
+    tele_category: Option<TeleCategory>,
+}
+
+/// Severity of a diagnostic message, used to color both its toast and its line in the log.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MsgLevel {
+    Info,
+    Warning,
+    Error,
 }
 
+impl MsgLevel {
+    fn color(self) -> Color32 {
+        match self {
+            MsgLevel::Info => Color32::from_rgb(90, 140, 220),
+            MsgLevel::Warning => Color32::from_rgb(210, 150, 40),
+            MsgLevel::Error => Color32::from_rgb(200, 60, 60),
+        }
+    }
+}
+
+/// A diagnostic sent over `msg_channel`: shown briefly as a toast, then kept in the scrollable
+/// log.
+#[derive(Debug, Clone)]
+struct Msg {
+    level: MsgLevel,
+    text: String,
+}
+
+/// An on-screen toast, auto-dismissed `TOAST_DURATION` after it was shown.
+struct Toast {
+    msg: Msg,
+    shown_at: Instant,
+}
+
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
 struct Stats {
     lines_count: usize,
     last_update_time: Instant,
@@ -57,34 +144,87 @@ struct Stats {
 
 struct PlotterApp {
     data_history: Vec<ConstGenericRingBuffer<f64, MAX_HISTORY_LEN>>,
-    msg_history: ConstGenericRingBuffer<String, MAX_MSGS>,
+    msg_history: ConstGenericRingBuffer<Msg, MAX_MSGS>,
+    toasts: Vec<Toast>,
     data_receiver: Receiver<SensorData>,
-    msg_receiver: Receiver<String>,
+    msg_sender: Sender<Msg>,
+    msg_receiver: Receiver<Msg>,
+    cmd_sender: Sender<io::DeviceCmd>,
+    event_receiver: Receiver<io::DeviceEvent>,
     tele_mode: TeleCategory,
-    tele_port: Box<dyn SerialPort>,
     stats: Stats,
+    available_ports: Vec<String>,
+    selected_in_port: Option<String>,
+    selected_out_port: Option<String>,
+    baud_rate: u32,
+    wire_format: WireFormat,
+    connected: bool,
+    data_sender: Sender<SensorData>,
+    recorder: Option<record::Recorder>,
+    record_format: record::RecordFormat,
+    record_path: String,
+    playback_format: record::RecordFormat,
+    playback_path: String,
+    playback_speed: f64,
+    playback_stop: Option<Sender<()>>,
+    playback_handle: Option<JoinHandle<()>>,
+    paused: bool,
+    y_lock: bool,
+    locked_y_min: f64,
+    locked_y_max: f64,
+    x_zoom: f64,
+    x_pan: usize,
 }
 
 impl PlotterApp {
     fn new(
+        data_sender: Sender<SensorData>,
         data_receiver: Receiver<SensorData>,
-        msg_receiver: Receiver<String>,
-        tele_port: Box<dyn SerialPort>,
+        msg_sender: Sender<Msg>,
+        msg_receiver: Receiver<Msg>,
+        cmd_sender: Sender<io::DeviceCmd>,
+        event_receiver: Receiver<io::DeviceEvent>,
+        settings: Settings,
     ) -> Self {
         let mut app = Self {
             data_history: Vec::new(),
             msg_history: ConstGenericRingBuffer::default(),
+            toasts: Vec::new(),
             data_receiver,
+            msg_sender,
             msg_receiver,
-            tele_mode: TeleCategory::None,
-            tele_port,
+            cmd_sender,
+            event_receiver,
+            tele_mode: settings.tele_mode,
             stats: Stats {
                 lines_count: 0,
                 last_update_time: Instant::now(),
                 lines_since_update: 0,
                 line_rate: 0.0,
             },
+            available_ports: Vec::new(),
+            selected_in_port: settings.selected_in_port,
+            selected_out_port: settings.selected_out_port,
+            baud_rate: settings.baud_rate,
+            wire_format: settings.wire_format,
+            connected: false,
+            data_sender,
+            recorder: None,
+            record_format: record::RecordFormat::Sqlite,
+            record_path: "recording.db".to_string(),
+            playback_format: record::RecordFormat::Sqlite,
+            playback_path: String::new(),
+            playback_speed: 1.0,
+            playback_stop: None,
+            playback_handle: None,
+            paused: false,
+            y_lock: settings.y_lock,
+            locked_y_min: settings.locked_y_min,
+            locked_y_max: settings.locked_y_max,
+            x_zoom: 1.0,
+            x_pan: 0,
         };
+        app.refresh_ports();
         app.apply_mode();
         app
     }
@@ -115,27 +255,177 @@ impl PlotterApp {
     }
 
     fn apply_mode(&mut self) {
+        self.apply_mode_local();
+        self.cmd_sender
+            .send(io::DeviceCmd::SetMode(self.tele_mode))
+            .unwrap();
+    }
+
+    /// Resizes `data_history`/`VALS_PER_LINE` for `self.tele_mode` without telling the device
+    /// thread anything. Used by offline playback, which must be able to switch chart layout for
+    /// a recorded log without sending a live `SetMode` to a device that may be connected.
+    fn apply_mode_local(&mut self) {
         let new_dim = Self::mode_to_dim(self.tele_mode);
         VALS_PER_LINE.store(new_dim, Ordering::Release);
         self.data_history = vec![ConstGenericRingBuffer::new(); new_dim];
-        self.tele_port
-            .write_all(format!("{}\n", self.tele_mode as u8).as_bytes())
+    }
+
+    fn refresh_ports(&mut self) {
+        self.available_ports = io::list_port_names();
+    }
+
+    fn notify(&self, level: MsgLevel, text: String) {
+        self.msg_sender.send(Msg { level, text }).unwrap();
+    }
+
+    fn connect(&mut self) {
+        if self.connected {
+            return;
+        }
+        let Some(out_port) = self.selected_out_port.clone() else {
+            self.notify(
+                MsgLevel::Warning,
+                "Select an output port before connecting.".to_string(),
+            );
+            return;
+        };
+        let Some(in_port) = self.selected_in_port.clone() else {
+            self.notify(
+                MsgLevel::Warning,
+                "Select an input port before connecting.".to_string(),
+            );
+            return;
+        };
+
+        self.cmd_sender
+            .send(io::DeviceCmd::Connect {
+                in_port,
+                out_port,
+                baud: self.baud_rate,
+                wire_format: self.wire_format,
+            })
             .unwrap();
     }
+
+    fn disconnect(&mut self) {
+        self.cmd_sender.send(io::DeviceCmd::Disconnect).unwrap();
+    }
+
+    fn reconnect(&mut self) {
+        self.cmd_sender.send(io::DeviceCmd::Reconnect).unwrap();
+    }
+
+    fn toggle_recording(&mut self) {
+        if self.recorder.take().is_some() {
+            self.notify(MsgLevel::Info, "Stopped recording.".to_string());
+            return;
+        }
+
+        match record::Recorder::open(Path::new(&self.record_path), self.record_format) {
+            Ok(recorder) => {
+                self.recorder = Some(recorder);
+                self.notify(
+                    MsgLevel::Info,
+                    format!("Recording to {}.", self.record_path),
+                );
+            }
+            Err(e) => {
+                self.notify(
+                    MsgLevel::Error,
+                    format!("Failed to start recording: {}", e),
+                );
+            }
+        }
+    }
+
+    fn start_playback(&mut self) {
+        self.stop_playback();
+
+        let path = PathBuf::from(&self.playback_path);
+        if let Some(mode) = record::tele_category_of(&path, self.playback_format) {
+            self.tele_mode = mode;
+            self.apply_mode_local();
+        }
+
+        let (stop_tx, stop_rx) = unbounded::<()>();
+        let data_sender = self.data_sender.clone();
+        let msg_sender = self.msg_sender.clone();
+        let format = self.playback_format;
+        let speed = self.playback_speed;
+        let handle = std::thread::spawn(move || {
+            record::playback_thread(path, format, speed, data_sender, msg_sender, stop_rx);
+        });
+
+        self.playback_stop = Some(stop_tx);
+        self.playback_handle = Some(handle);
+    }
+
+    fn stop_playback(&mut self) {
+        if let Some(stop_tx) = self.playback_stop.take() {
+            let _ = stop_tx.send(());
+        }
+        if let Some(handle) = self.playback_handle.take() {
+            let _ = handle.join();
+        }
+    }
 }
 
 impl eframe::App for PlotterApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        let settings = Settings {
+            tele_mode: self.tele_mode,
+            selected_in_port: self.selected_in_port.clone(),
+            selected_out_port: self.selected_out_port.clone(),
+            baud_rate: self.baud_rate,
+            wire_format: self.wire_format,
+            y_lock: self.y_lock,
+            locked_y_min: self.locked_y_min,
+            locked_y_max: self.locked_y_max,
+        };
+        eframe::set_value(storage, eframe::APP_KEY, &settings);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         let now = Instant::now();
 
-        while let Ok(new_data) = self.data_receiver.try_recv() {
-            let vals = VALS_PER_LINE.load(Ordering::Acquire);
-            if vals > 0 {
+        if !self.paused {
+            while let Ok(new_data) = self.data_receiver.try_recv() {
+                // A playback recording can legitimately span more than one tele mode if the
+                // user switched mode without stopping the recording; follow the sample's own
+                // recorded mode rather than trusting the dimension fixed at playback start.
+                if let Some(category) = new_data.tele_category {
+                    if category != self.tele_mode {
+                        self.tele_mode = category;
+                        self.apply_mode_local();
+                    }
+                }
+
+                let vals = VALS_PER_LINE.load(Ordering::Acquire);
+                if vals == 0 {
+                    continue;
+                }
+                if new_data.values.len() != vals {
+                    self.notify(
+                        MsgLevel::Warning,
+                        format!(
+                            "Skipping sample with unexpected dimension (expected {}, got {})",
+                            vals,
+                            new_data.values.len()
+                        ),
+                    );
+                    continue;
+                }
+
                 self.stats.lines_count += 1;
                 for i in 0..vals {
                     self.data_history[i].enqueue(new_data.values[i]);
                 }
                 self.stats.lines_since_update += 1;
+                if let Some(recorder) = self.recorder.as_mut() {
+                    if let Err(e) = recorder.record(self.tele_mode, &new_data.values) {
+                        self.notify(MsgLevel::Error, format!("Failed to record sample: {}", e));
+                    }
+                }
             }
         }
 
@@ -170,12 +460,192 @@ impl eframe::App for PlotterApp {
                         }
                     });
             });
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.label(if self.connected {
+                    "Connected"
+                } else {
+                    "Disconnected"
+                });
+
+                ui.label("In:");
+                egui::ComboBox::from_label("In port")
+                    .selected_text(self.selected_in_port.as_deref().unwrap_or("<select>"))
+                    .show_ui(ui, |ui| {
+                        for port in self.available_ports.clone() {
+                            ui.selectable_value(
+                                &mut self.selected_in_port,
+                                Some(port.clone()),
+                                port,
+                            );
+                        }
+                    });
+
+                ui.label("Out:");
+                egui::ComboBox::from_label("Out port")
+                    .selected_text(self.selected_out_port.as_deref().unwrap_or("<select>"))
+                    .show_ui(ui, |ui| {
+                        for port in self.available_ports.clone() {
+                            ui.selectable_value(
+                                &mut self.selected_out_port,
+                                Some(port.clone()),
+                                port,
+                            );
+                        }
+                    });
+
+                ui.label("Baud:");
+                egui::ComboBox::from_label("Baud rate")
+                    .selected_text(self.baud_rate.to_string())
+                    .show_ui(ui, |ui| {
+                        for baud in BAUD_RATES {
+                            if ui
+                                .selectable_value(&mut self.baud_rate, baud, baud.to_string())
+                                .clicked()
+                            {
+                                self.cmd_sender.send(io::DeviceCmd::SetBaud(baud)).unwrap();
+                            }
+                        }
+                    });
+
+                ui.label("Format:");
+                egui::ComboBox::from_label("Wire format")
+                    .selected_text(self.wire_format.as_ref())
+                    .show_ui(ui, |ui| {
+                        for option in WireFormat::iter() {
+                            ui.selectable_value(&mut self.wire_format, option, option.as_ref());
+                        }
+                    });
+
+                if ui.button("Refresh ports").clicked() {
+                    self.refresh_ports();
+                }
+                if self.connected {
+                    if ui.button("Disconnect").clicked() {
+                        self.disconnect();
+                    }
+                    if ui.button("Reconnect").clicked() {
+                        self.reconnect();
+                    }
+                } else if ui.button("Connect").clicked() {
+                    self.connect();
+                }
+            });
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.label("Record:");
+                egui::ComboBox::from_label("Record format")
+                    .selected_text(match self.record_format {
+                        record::RecordFormat::Sqlite => "SQLite",
+                        record::RecordFormat::Csv => "CSV",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.record_format,
+                            record::RecordFormat::Sqlite,
+                            "SQLite",
+                        );
+                        ui.selectable_value(
+                            &mut self.record_format,
+                            record::RecordFormat::Csv,
+                            "CSV",
+                        );
+                    });
+                ui.add(egui::TextEdit::singleline(&mut self.record_path).desired_width(140.0));
+                let record_label = if self.recorder.is_some() {
+                    "Stop recording"
+                } else {
+                    "Record"
+                };
+                if ui.button(record_label).clicked() {
+                    self.toggle_recording();
+                }
+
+                ui.separator();
+
+                ui.label("Playback:");
+                ui.add(egui::TextEdit::singleline(&mut self.playback_path).desired_width(140.0));
+                egui::ComboBox::from_label("Playback format")
+                    .selected_text(match self.playback_format {
+                        record::RecordFormat::Sqlite => "SQLite",
+                        record::RecordFormat::Csv => "CSV",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.playback_format,
+                            record::RecordFormat::Sqlite,
+                            "SQLite",
+                        );
+                        ui.selectable_value(
+                            &mut self.playback_format,
+                            record::RecordFormat::Csv,
+                            "CSV",
+                        );
+                    });
+                egui::ComboBox::from_label("Playback speed")
+                    .selected_text(format!("{}x", self.playback_speed))
+                    .show_ui(ui, |ui| {
+                        for speed in PLAYBACK_SPEEDS {
+                            ui.selectable_value(
+                                &mut self.playback_speed,
+                                speed,
+                                format!("{}x", speed),
+                            );
+                        }
+                    });
+                if self.playback_handle.is_some() {
+                    if ui.button("Stop playback").clicked() {
+                        self.stop_playback();
+                    }
+                } else if ui.button("Play").clicked() {
+                    self.start_playback();
+                }
+            });
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.label(if self.paused { "PAUSED" } else { "LIVE" });
+                if ui.button(if self.paused { "Resume" } else { "Pause" }).clicked() {
+                    self.paused = !self.paused;
+                }
+
+                ui.separator();
+
+                ui.checkbox(&mut self.y_lock, "Lock Y");
+                ui.label("min:");
+                ui.add(egui::DragValue::new(&mut self.locked_y_min).speed(0.1));
+                ui.label("max:");
+                ui.add(egui::DragValue::new(&mut self.locked_y_max).speed(0.1));
+
+                ui.separator();
+
+                ui.label("Zoom:");
+                ui.add(egui::Slider::new(&mut self.x_zoom, 0.02..=1.0));
+                ui.label("Scroll back:");
+                ui.add(egui::Slider::new(&mut self.x_pan, 0..=MAX_HISTORY_LEN));
+            });
             ui.add_space(2.0);
         });
 
+        for event in self.event_receiver.try_iter() {
+            match event {
+                io::DeviceEvent::Connected => self.connected = true,
+                io::DeviceEvent::ConnectFailed => self.connected = false,
+                io::DeviceEvent::Disconnected => self.connected = false,
+            }
+        }
+
+        for msg in self.msg_receiver.try_iter() {
+            self.toasts.push(Toast {
+                msg: msg.clone(),
+                shown_at: now,
+            });
+            self.msg_history.enqueue(msg);
+        }
+        self.toasts
+            .retain(|toast| now.duration_since(toast.shown_at) < TOAST_DURATION);
+
         TopBottomPanel::bottom("msg_panel").show(ctx, |ui| {
             ui.add_space(5.0);
-            self.msg_history.extend(self.msg_receiver.try_iter());
             if !self.msg_history.is_empty() {
                 ScrollArea::vertical()
                     .max_width(f32::INFINITY)
@@ -183,7 +653,9 @@ impl eframe::App for PlotterApp {
                     .max_height(50.0)
                     .show(ui, |ui| {
                         for msg in self.msg_history.iter() {
-                            ui.label(egui::RichText::new(msg).color(egui::Color32::RED));
+                            ui.label(
+                                egui::RichText::new(&msg.text).color(msg.level.color()),
+                            );
                         }
                     });
             } else {
@@ -192,6 +664,18 @@ impl eframe::App for PlotterApp {
             ui.add_space(5.0);
         });
 
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -60.0))
+            .show(ctx, |ui| {
+                for toast in self.toasts.iter().rev() {
+                    egui::Frame::popup(ui.style())
+                        .fill(toast.msg.level.color())
+                        .show(ui, |ui| {
+                            ui.label(egui::RichText::new(&toast.msg.text).color(Color32::WHITE));
+                        });
+                }
+            });
+
         CentralPanel::default().show(ctx, |ui| {
             egui::Frame::canvas(ui.style())
                 .fill(Color32::from_white_alpha(0))
@@ -200,21 +684,32 @@ impl eframe::App for PlotterApp {
                     let root = EguiBackend::new(ui_plot).into_drawing_area();
                     root.fill(&BLUEGREY_700).unwrap();
 
-                    // Define X-axis range (based on sample count).
-                    let min_x = (self.stats.lines_count as f64 - MAX_HISTORY_LEN as f64).max(0.0);
-                    let max_x = self.stats.lines_count as f64;
+                    // X-axis range: a zoomable, scrollable window into the ring buffer.
+                    let visible_len = (MAX_HISTORY_LEN as f64 * self.x_zoom).max(1.0);
+                    let max_x = (self.stats.lines_count as f64 - self.x_pan as f64).max(0.0);
+                    let min_x = (max_x - visible_len).max(0.0);
 
                     let has_data =
                         !self.data_history.is_empty() && !self.data_history[0].is_empty();
-                    // Determine Y-axis range for auto-scaling.
-                    let mut min_y = if has_data { f64::MAX } else { 0.0 };
-                    let mut max_y = if has_data { f64::MIN } else { 0.0 };
-                    for series_data in &self.data_history {
-                        for &val in series_data.iter() {
-                            min_y = min_y.min(val);
-                            max_y = max_y.max(val);
+                    // Determine Y-axis range: either the user's locked range, or auto-scaled
+                    // over all of data_history.
+                    let (min_y, max_y) = if self.y_lock {
+                        if self.locked_y_max > self.locked_y_min {
+                            (self.locked_y_min, self.locked_y_max)
+                        } else {
+                            (self.locked_y_min, self.locked_y_min + 1.0)
                         }
-                    }
+                    } else {
+                        let mut min_y = if has_data { f64::MAX } else { 0.0 };
+                        let mut max_y = if has_data { f64::MIN } else { 0.0 };
+                        for series_data in &self.data_history {
+                            for &val in series_data.iter() {
+                                min_y = min_y.min(val);
+                                max_y = max_y.max(val);
+                            }
+                        }
+                        (min_y, max_y)
+                    };
 
                     let mut chart = ChartBuilder::on(&root)
                         .x_label_area_size(30)
@@ -259,22 +754,35 @@ fn main() -> eframe::Result {
     };
 
     let (tx, rx) = unbounded::<SensorData>();
-    let (etx, erx) = unbounded::<String>();
+    let (etx, erx) = unbounded::<Msg>();
+    let (cmd_tx, cmd_rx) = unbounded::<io::DeviceCmd>();
+    let (event_tx, event_rx) = unbounded::<io::DeviceEvent>();
 
+    let device_data_sender = tx.clone();
+    let device_msg_sender = etx.clone();
     std::thread::spawn(move || {
-        io::input_thread(tx, etx);
+        io::device_thread(cmd_rx, device_data_sender, device_msg_sender, event_tx);
     });
 
-    let tele_port = match io::open_out_port() {
-        Ok(port) => port,
-        Err(e) => {
-            return eframe::Result::Err(eframe::Error::AppCreation(Box::new(e)));
-        }
-    };
+    let app_data_sender = tx.clone();
 
     eframe::run_native(
         "Drone Stream Plotter",
         options,
-        Box::new(move |_cc| Ok(Box::new(PlotterApp::new(rx, erx, tele_port)))),
+        Box::new(move |cc| {
+            let settings = cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+                .unwrap_or_default();
+            Ok(Box::new(PlotterApp::new(
+                app_data_sender,
+                rx,
+                etx,
+                erx,
+                cmd_tx,
+                event_rx,
+                settings,
+            )))
+        }),
     )
 }
@@ -0,0 +1,217 @@
+use crossbeam_channel::{Receiver, Sender};
+use rusqlite::{Connection, params};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::{Msg, MsgLevel, SensorData, TeleCategory};
+
+/// On-disk encoding for recorded telemetry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordFormat {
+    Sqlite,
+    Csv,
+}
+
+enum RecordSink {
+    Sqlite(Connection),
+    Csv(File),
+}
+
+/// Persists accepted telemetry samples to disk, tagged with the telemetry category and a
+/// monotonic timestamp so a flight can be replayed later without the hardware attached.
+pub struct Recorder {
+    sink: RecordSink,
+    start: Instant,
+}
+
+impl Recorder {
+    pub fn open(path: &Path, format: RecordFormat) -> Result<Self, String> {
+        let sink = match format {
+            RecordFormat::Sqlite => {
+                let conn = Connection::open(path).map_err(|e| e.to_string())?;
+                // Mirror the CSV sink's truncate-on-open behavior: otherwise rows from a prior
+                // session under the same path would interleave with this one by timestamp_ms.
+                conn.execute("DROP TABLE IF EXISTS readings", [])
+                    .map_err(|e| e.to_string())?;
+                conn.execute(
+                    "CREATE TABLE readings (
+                        timestamp_ms  INTEGER NOT NULL,
+                        tele_category INTEGER NOT NULL,
+                        values_csv    TEXT NOT NULL
+                    )",
+                    [],
+                )
+                .map_err(|e| e.to_string())?;
+                RecordSink::Sqlite(conn)
+            }
+            RecordFormat::Csv => {
+                let mut file = File::create(path).map_err(|e| e.to_string())?;
+                writeln!(file, "timestamp_ms,tele_category,values").map_err(|e| e.to_string())?;
+                RecordSink::Csv(file)
+            }
+        };
+        Ok(Self {
+            sink,
+            start: Instant::now(),
+        })
+    }
+
+    /// Records one sample, tagged with the telemetry category it was captured under and the
+    /// elapsed time since this `Recorder` was opened.
+    pub fn record(&mut self, tele_mode: TeleCategory, values: &[f64]) -> Result<(), String> {
+        let timestamp_ms = self.start.elapsed().as_millis() as i64;
+        let values_csv = values
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        match &mut self.sink {
+            RecordSink::Sqlite(conn) => conn
+                .execute(
+                    "INSERT INTO readings (timestamp_ms, tele_category, values_csv) VALUES (?1, ?2, ?3)",
+                    params![timestamp_ms, tele_mode as i64, values_csv],
+                )
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            RecordSink::Csv(file) => {
+                writeln!(file, "{},{},{}", timestamp_ms, tele_mode as u8, values_csv)
+                    .map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+struct PlaybackRow {
+    timestamp_ms: i64,
+    tele_category: u8,
+    values: Vec<f64>,
+}
+
+fn read_rows(path: &Path, format: RecordFormat) -> Result<Vec<PlaybackRow>, String> {
+    match format {
+        RecordFormat::Sqlite => {
+            let conn = Connection::open(path).map_err(|e| e.to_string())?;
+            let mut stmt = conn
+                .prepare(
+                    "SELECT timestamp_ms, tele_category, values_csv FROM readings ORDER BY timestamp_ms",
+                )
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map([], |row| {
+                    let timestamp_ms: i64 = row.get(0)?;
+                    let tele_category: i64 = row.get(1)?;
+                    let values_csv: String = row.get(2)?;
+                    Ok((timestamp_ms, tele_category as u8, values_csv))
+                })
+                .map_err(|e| e.to_string())?;
+
+            Ok(rows
+                .filter_map(Result::ok)
+                .map(|(timestamp_ms, tele_category, values_csv)| PlaybackRow {
+                    timestamp_ms,
+                    tele_category,
+                    values: parse_values_csv(&values_csv),
+                })
+                .collect())
+        }
+        RecordFormat::Csv => {
+            let file = File::open(path).map_err(|e| e.to_string())?;
+            let mut rows = Vec::new();
+            for line in BufReader::new(file).lines().skip(1) {
+                let line = line.map_err(|e| e.to_string())?;
+                let mut fields = line.splitn(3, ',');
+                let timestamp_ms: i64 = fields.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+                let tele_category: u8 = fields.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+                let values = parse_values_csv(fields.next().unwrap_or(""));
+                rows.push(PlaybackRow {
+                    timestamp_ms,
+                    tele_category,
+                    values,
+                });
+            }
+            Ok(rows)
+        }
+    }
+}
+
+fn parse_values_csv(values_csv: &str) -> Vec<f64> {
+    values_csv
+        .split(',')
+        .filter_map(|s| s.parse().ok())
+        .collect()
+}
+
+/// Reads the `TeleCategory` the first recorded sample was captured under, so the UI can switch
+/// to the matching channel count and labels before playback starts.
+pub fn tele_category_of(path: &Path, format: RecordFormat) -> Option<TeleCategory> {
+    let rows = read_rows(path, format).ok()?;
+    TeleCategory::from_u8(rows.first()?.tele_category)
+}
+
+/// Waits up to `dur` for `stop_receiver` to fire, returning `false` as soon as it does (or the
+/// sender is dropped) and `true` if `dur` elapsed first. Used instead of `thread::sleep` so a
+/// recording with a long inter-sample gap doesn't make `stop_playback` block the UI thread.
+fn wait_or_stop(dur: Duration, stop_receiver: &Receiver<()>) -> bool {
+    match stop_receiver.recv_timeout(dur) {
+        Err(e) if e.is_timeout() => true,
+        _ => false,
+    }
+}
+
+/// Feeds `data_sender` from a recorded file at `speed`x real-time, preserving the original
+/// inter-sample gaps. Runs until the file is exhausted or a value arrives on `stop_receiver`.
+pub fn playback_thread(
+    path: PathBuf,
+    format: RecordFormat,
+    speed: f64,
+    data_sender: Sender<SensorData>,
+    msg_sender: Sender<Msg>,
+    stop_receiver: Receiver<()>,
+) {
+    let rows = match read_rows(&path, format) {
+        Ok(rows) => rows,
+        Err(e) => {
+            msg_sender
+                .send(Msg {
+                    level: MsgLevel::Error,
+                    text: format!("Failed to open recording {}: {}", path.display(), e),
+                })
+                .unwrap();
+            return;
+        }
+    };
+
+    let speed = if speed > 0.0 { speed } else { 1.0 };
+    let mut prev_timestamp_ms: Option<i64> = None;
+
+    for row in rows {
+        if stop_receiver.try_recv().is_ok() {
+            break;
+        }
+
+        if let Some(prev) = prev_timestamp_ms {
+            let delta_ms = (row.timestamp_ms - prev).max(0) as f64 / speed;
+            if !wait_or_stop(Duration::from_millis(delta_ms as u64), &stop_receiver) {
+                break;
+            }
+        }
+        prev_timestamp_ms = Some(row.timestamp_ms);
+
+        data_sender
+            .send(SensorData {
+                values: row.values,
+                tele_category: TeleCategory::from_u8(row.tele_category),
+            })
+            .unwrap();
+    }
+
+    msg_sender
+        .send(Msg {
+            level: MsgLevel::Info,
+            text: format!("Playback of {} finished.", path.display()),
+        })
+        .unwrap();
+}